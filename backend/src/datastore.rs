@@ -0,0 +1,5 @@
+pub mod file;
+pub mod memory;
+pub mod object_store;
+pub mod postgres;
+pub mod post;
@@ -1,62 +1,97 @@
 use crate::datastore::post::{DataStore, PostData, Slug};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::{
-    fs::{File, OpenOptions},
-    io::{Read, Write},
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 /// Post の front matter のデータ
 #[derive(Deserialize, Serialize, PartialEq, Debug)]
 struct PostFrontMatter {
     pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// キャッシュ 1 件分。パース済み PostData と、その元ファイルの mtime を持つ。
+#[derive(Clone)]
+struct CacheEntry {
+    mtime: SystemTime,
+    postdata: PostData,
 }
 
 /// datastore on file system
+///
+/// 読み出しの hot path でディスクアクセスを避けるため、slug をキーにした
+/// lock-free な concurrent map で PostData をキャッシュする。
 pub struct FileDataStore {
     pub posts_dir_path: PathBuf,
+    cache: scc::HashMap<Slug, CacheEntry>,
 }
 
 #[derive(PartialEq, Debug, thiserror::Error)]
-enum FileDataStoreErrors {
+pub(crate) enum FileDataStoreErrors {
     #[error("MissingFrontMatter")]
     MissingFrontMatter,
 }
 
-/// Post を path で指定して読み出す。
-fn read_post_path(path: &Path) -> Result<PostData, Box<dyn std::error::Error>> {
-    let slug = path.file_stem().unwrap().to_str().unwrap().to_string();
-    let mut file = File::open(path)?;
-
-    let mut cont = String::new();
-    let _n = file.read_to_string(&mut cont)?;
-    let (front_matter, content) = match serde_frontmatter::deserialize::<PostFrontMatter>(&cont) {
+/// front matter 付き Markdown を PostData にパースする。
+///
+/// serde_frontmatter のパースは CPU バウンドなので、呼び出し側は必要に応じて
+/// `spawn_blocking` で実行すること。file / object_store の両 backend で共用する。
+pub(crate) fn parse_post(slug: String, cont: &str) -> Result<PostData, FileDataStoreErrors> {
+    let (front_matter, content) = match serde_frontmatter::deserialize::<PostFrontMatter>(cont) {
         Ok(v) => v,
-        Err(_) => return Err(Box::new(FileDataStoreErrors::MissingFrontMatter)),
+        Err(_) => return Err(FileDataStoreErrors::MissingFrontMatter),
     };
-    let PostFrontMatter { title } = front_matter;
-    let postdata = PostData {
+    let PostFrontMatter { title, date, tags } = front_matter;
+    Ok(PostData {
         title,
         slug,
         content: content.trim().to_string(),
-    };
-    log::trace!("{:?}", postdata);
-    Ok(postdata)
+        date,
+        tags,
+    })
 }
 
-/// PostData からファイルシステム操作のためのデータを構築する
-fn build_write_data(filedatastore: &FileDataStore, postdata: &PostData) -> (PathBuf, String) {
-    // make data
+/// PostData を front matter 付き Markdown にシリアライズする。
+pub(crate) fn serialize_post(postdata: &PostData) -> String {
     let PostData {
         title,
-        slug,
+        slug: _,
         content,
+        date,
+        tags,
     } = postdata;
     let front_matter = PostFrontMatter {
         title: title.clone(),
+        date: *date,
+        tags: tags.clone(),
     };
-    let markdown = serde_frontmatter::serialize(front_matter, content.trim()).unwrap();
-    let path = filedatastore.slug_to_path(slug);
+    serde_frontmatter::serialize(front_matter, content.trim()).unwrap()
+}
+
+/// Post を path で指定して読み出す。
+async fn read_post_path(
+    path: &Path,
+) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+    let slug = path.file_stem().unwrap().to_str().unwrap().to_string();
+    let mut file = tokio::fs::File::open(path).await?;
+
+    let mut cont = String::new();
+    let _n = file.read_to_string(&mut cont).await?;
+    // serde_frontmatter のパースは CPU バウンドなので spawn_blocking に逃がす。
+    let postdata = tokio::task::spawn_blocking(move || parse_post(slug, &cont)).await??;
+    log::trace!("{:?}", postdata);
+    Ok(postdata)
+}
+
+/// PostData からファイルシステム操作のためのデータを構築する
+fn build_write_data(filedatastore: &FileDataStore, postdata: &PostData) -> (PathBuf, String) {
+    let markdown = serialize_post(postdata);
+    let path = filedatastore.slug_to_path(&postdata.slug);
     (path, markdown)
 }
 
@@ -64,62 +99,102 @@ impl FileDataStore {
     /// Constructor
     pub fn new(posts_dir: &str) -> FileDataStore {
         let posts_dir_path = Path::new(posts_dir).to_path_buf();
-        FileDataStore { posts_dir_path }
+        FileDataStore {
+            posts_dir_path,
+            cache: scc::HashMap::new(),
+        }
     }
     /// slug から path を作成する。
     fn slug_to_path(&self, slug: &str) -> PathBuf {
-        let FileDataStore { posts_dir_path } = self;
-        let path = posts_dir_path.join(slug).with_extension("md");
+        let path = self.posts_dir_path.join(slug).with_extension("md");
         log::trace!("{:?}", path);
         path
     }
 }
 
+#[async_trait]
 impl DataStore for FileDataStore {
     /// Create
-    fn create_post(&self, postdata: &PostData) -> Result<PostData, Box<dyn std::error::Error>> {
+    async fn create_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
         let (path, markdown) = build_write_data(self, postdata);
         // write
-        let mut file = File::create(path)?;
-        let _n = file.write(markdown.as_bytes());
+        let mut file = tokio::fs::File::create(path).await?;
+        file.write_all(markdown.as_bytes()).await?;
+        let _ = self.cache.remove_async(&postdata.slug).await;
         let postdata = postdata.clone();
         log::trace!("{:?}", postdata);
         Ok(postdata)
     }
     /// Read
-    fn read_post(&self, slug: &str) -> Result<PostData, Box<dyn std::error::Error>> {
+    async fn read_post(
+        &self,
+        slug: &str,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
         let path = self.slug_to_path(slug);
-        read_post_path(&path)
+        let mtime = tokio::fs::metadata(&path).await?.modified()?;
+        // cache hit: ファイルが書き換わっていなければキャッシュを返す。
+        if let Some(entry) = self.cache.read_async(slug, |_, v| v.clone()).await {
+            if entry.mtime == mtime {
+                log::trace!("cache hit: {}", slug);
+                return Ok(entry.postdata);
+            }
+        }
+        // cache miss / mtime 更新: 読み直してキャッシュを張り替える。
+        let postdata = read_post_path(&path).await?;
+        let _ = self.cache.remove_async(slug).await;
+        let _ = self
+            .cache
+            .insert_async(
+                slug.to_string(),
+                CacheEntry {
+                    mtime,
+                    postdata: postdata.clone(),
+                },
+            )
+            .await;
+        Ok(postdata)
     }
     /// List
-    fn list_posts(&self) -> Result<Vec<Slug>, Box<dyn std::error::Error>> {
+    async fn list_posts(&self) -> Result<Vec<Slug>, Box<dyn std::error::Error + Send + Sync>> {
         let mut slug_vec: Vec<Slug> = Vec::new();
-        let FileDataStore { posts_dir_path } = self;
-        let paths = std::fs::read_dir(posts_dir_path)?;
-        for direntry_result in paths {
-            let path = direntry_result?.path();
+        let posts_dir_path = &self.posts_dir_path;
+        let mut paths = tokio::fs::read_dir(posts_dir_path).await?;
+        while let Some(direntry) = paths.next_entry().await? {
+            let path = direntry.path();
             slug_vec.push(path.file_stem().unwrap().to_str().unwrap().to_string());
         }
         Ok(slug_vec)
     }
     /// Update
-    fn update_post(&self, postdata: &PostData) -> Result<PostData, Box<dyn std::error::Error>> {
+    async fn update_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
         let (path, markdown) = build_write_data(self, postdata);
         // write
-        let mut file = OpenOptions::new()
+        let mut file = tokio::fs::OpenOptions::new()
             .write(true)
             .create(false)
             .truncate(true)
-            .open(path)?;
-        let _n = file.write(markdown.as_bytes());
+            .open(path)
+            .await?;
+        file.write_all(markdown.as_bytes()).await?;
+        let _ = self.cache.remove_async(&postdata.slug).await;
         let postdata = postdata.clone();
         log::trace!("{:?}", postdata);
         Ok(postdata)
     }
     /// Delete
-    fn delete_post(&self, slug: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let path = self.slug_to_path(&slug);
-        let ret = std::fs::remove_file(path)?;
+    async fn delete_post(
+        &self,
+        slug: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let path = self.slug_to_path(slug);
+        let ret = tokio::fs::remove_file(path).await?;
+        let _ = self.cache.remove_async(slug).await;
         Ok(ret)
     }
 }
@@ -127,18 +202,18 @@ impl DataStore for FileDataStore {
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[test]
-    fn create_post_delete_post_success() {
+    #[tokio::test]
+    async fn create_post_delete_post_success() {
         let _ = pretty_env_logger::try_init();
         let filedatastore = FileDataStore::new("./example/posts");
         let slug = "sample3";
 
         // prepare
         let path = filedatastore.slug_to_path(slug);
-        let _ = std::fs::remove_file(path);
+        let _ = tokio::fs::remove_file(path).await;
 
         // check before create
-        let readdata_before = filedatastore.read_post(&slug);
+        let readdata_before = filedatastore.read_post(slug).await;
         assert!(readdata_before.is_err());
 
         // create
@@ -146,82 +221,88 @@ mod tests {
             title: String::from("Sample Post 3"),
             slug: String::from(slug),
             content: String::from("a test body"),
+            date: None,
+            tags: Vec::new(),
         };
         log::trace!("createdata: {:?}", createdata);
-        let retdata = filedatastore.create_post(&createdata).unwrap();
+        let retdata = filedatastore.create_post(&createdata).await.unwrap();
         log::trace!("retdata: {:?}", retdata);
         assert!(retdata.eq(&createdata));
 
         // check after create
-        let readdata = filedatastore.read_post(slug).unwrap();
+        let readdata = filedatastore.read_post(slug).await.unwrap();
         log::trace!("readdata: {:?}", readdata);
         assert!(readdata.eq(&createdata));
 
         // delete
-        let delresult = filedatastore.delete_post(&createdata.slug);
+        let delresult = filedatastore.delete_post(&createdata.slug).await;
         assert!(delresult.is_ok());
     }
-    #[test]
-    fn read_post_success() {
+    #[tokio::test]
+    async fn read_post_success() {
         let _ = pretty_env_logger::try_init();
         let filedatastore = FileDataStore::new("./example/posts");
         let slug = "sample1";
-        let post = filedatastore.read_post(slug).unwrap();
+        let post = filedatastore.read_post(slug).await.unwrap();
         assert!(post.slug.eq("sample1"));
         assert!(post.title.eq("sample 1"));
     }
-    #[test]
-    fn list_posts_success() {
+    #[tokio::test]
+    async fn list_posts_success() {
         let _ = pretty_env_logger::try_init();
         let filedatastore = FileDataStore::new("./example/posts");
-        let slug_vec = filedatastore.list_posts().unwrap();
+        let slug_vec = filedatastore.list_posts().await.unwrap();
         eprintln!("{:?}", slug_vec);
-        assert!(slug_vec[0].eq("sample1"));
-        assert!(slug_vec[1].eq("sample2"));
+        assert!(slug_vec.contains(&String::from("sample1")));
+        assert!(slug_vec.contains(&String::from("sample2")));
     }
-    #[test]
-    fn list_posts_not_exists() {
+    #[tokio::test]
+    async fn list_posts_not_exists() {
         let _ = pretty_env_logger::try_init();
         let posts_dir = "./this file does not exists";
         let filedatastore = FileDataStore::new(posts_dir);
-        let metadata = filedatastore.list_posts();
+        let metadata = filedatastore.list_posts().await;
         assert!(metadata.is_err());
     }
-    #[test]
-    fn update_post_success() {
+    #[tokio::test]
+    async fn update_post_success() {
         let _ = pretty_env_logger::try_init();
         let filedatastore = FileDataStore::new("./example/posts");
         let slug = "sample2";
 
         // check before update
-        let readdata_before = filedatastore.read_post(&slug);
+        let readdata_before = filedatastore.read_post(slug).await;
         assert!(readdata_before.is_ok());
         let original_postdata = readdata_before.unwrap();
         let PostData {
             title,
             slug,
             content,
+            date,
+            tags,
         } = original_postdata.clone();
         assert!(!content.eq("hoge"));
 
         // update
         let updatedata = PostData {
-            title: title.clone(),
+            title,
             slug: slug.clone(),
             content: String::from("hoge"),
+            date,
+            tags,
         };
         log::trace!("createdata: {:?}", updatedata);
-        let retdata = filedatastore.update_post(&updatedata).unwrap();
+        let retdata = filedatastore.update_post(&updatedata).await.unwrap();
         log::trace!("retdata: {:?}", retdata);
         assert!(retdata.eq(&updatedata));
 
         // check after create
-        let readdata = filedatastore.read_post(&slug).unwrap();
+        let readdata = filedatastore.read_post(&slug).await.unwrap();
         log::trace!("readdata: {:?}", readdata);
         assert!(readdata.eq(&updatedata));
 
         // finalize
-        let finiret = filedatastore.update_post(&original_postdata).unwrap();
+        let finiret = filedatastore.update_post(&original_postdata).await.unwrap();
         assert!(finiret.eq(&original_postdata));
     }
 }
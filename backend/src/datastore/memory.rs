@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+
+use crate::datastore::post::{DataStore, PostData, Slug};
+
+/// datastore on memory
+///
+/// ディスクに触れずに resolver やテストを動かすための in-memory 実装。
+#[derive(Default)]
+pub struct MemoryDataStore {
+    posts: RwLock<HashMap<Slug, PostData>>,
+}
+
+#[derive(PartialEq, Debug, thiserror::Error)]
+enum MemoryDataStoreErrors {
+    #[error("NotFound")]
+    NotFound,
+}
+
+impl MemoryDataStore {
+    /// Constructor
+    pub fn new() -> MemoryDataStore {
+        MemoryDataStore::default()
+    }
+}
+
+#[async_trait]
+impl DataStore for MemoryDataStore {
+    /// Create
+    async fn create_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+        let mut posts = self.posts.write().unwrap();
+        posts.insert(postdata.slug.clone(), postdata.clone());
+        log::trace!("{:?}", postdata);
+        Ok(postdata.clone())
+    }
+    /// Read
+    async fn read_post(
+        &self,
+        slug: &str,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+        let posts = self.posts.read().unwrap();
+        match posts.get(slug) {
+            Some(postdata) => Ok(postdata.clone()),
+            None => Err(Box::new(MemoryDataStoreErrors::NotFound)),
+        }
+    }
+    /// List
+    async fn list_posts(&self) -> Result<Vec<Slug>, Box<dyn std::error::Error + Send + Sync>> {
+        let posts = self.posts.read().unwrap();
+        Ok(posts.keys().cloned().collect())
+    }
+    /// Update
+    async fn update_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+        let mut posts = self.posts.write().unwrap();
+        if !posts.contains_key(&postdata.slug) {
+            return Err(Box::new(MemoryDataStoreErrors::NotFound));
+        }
+        posts.insert(postdata.slug.clone(), postdata.clone());
+        log::trace!("{:?}", postdata);
+        Ok(postdata.clone())
+    }
+    /// Delete
+    async fn delete_post(
+        &self,
+        slug: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut posts = self.posts.write().unwrap();
+        match posts.remove(slug) {
+            Some(_) => Ok(()),
+            None => Err(Box::new(MemoryDataStoreErrors::NotFound)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[tokio::test]
+    async fn create_read_update_delete_success() {
+        let _ = pretty_env_logger::try_init();
+        let store = MemoryDataStore::new();
+        let slug = "sample1";
+
+        // check before create
+        assert!(store.read_post(slug).await.is_err());
+
+        // create
+        let createdata = PostData {
+            title: String::from("sample 1"),
+            slug: String::from(slug),
+            content: String::from("a test body"),
+            date: None,
+            tags: Vec::new(),
+        };
+        assert!(store.create_post(&createdata).await.unwrap().eq(&createdata));
+        assert!(store.read_post(slug).await.unwrap().eq(&createdata));
+
+        // update
+        let updatedata = PostData {
+            content: String::from("hoge"),
+            ..createdata.clone()
+        };
+        assert!(store.update_post(&updatedata).await.unwrap().eq(&updatedata));
+        assert!(store.read_post(slug).await.unwrap().eq(&updatedata));
+
+        // delete
+        assert!(store.delete_post(slug).await.is_ok());
+        assert!(store.read_post(slug).await.is_err());
+    }
+}
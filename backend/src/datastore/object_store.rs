@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use url::Url;
+
+use crate::datastore::file::{parse_post, serialize_post};
+use crate::datastore::post::{DataStore, PostData, Slug};
+
+/// datastore on object storage (S3 / GCS / Azure Blob)
+///
+/// slug を `<prefix>/<slug>.md` というオブジェクトキーに対応付け、
+/// front matter 付き Markdown を本体として put / get / delete / list する。
+pub struct ObjectDataStore {
+    store: Box<dyn ObjectStore>,
+    prefix: ObjectPath,
+}
+
+impl ObjectDataStore {
+    /// 接続 URL (例: `s3://bucket/posts`) から datastore を構築する。
+    pub fn new(url: &str) -> Result<ObjectDataStore, Box<dyn std::error::Error + Send + Sync>> {
+        let parsed = Url::parse(url)?;
+        let (store, prefix) = object_store::parse_url(&parsed)?;
+        Ok(ObjectDataStore { store, prefix })
+    }
+    /// slug からオブジェクトキーを作成する。
+    fn slug_to_key(&self, slug: &str) -> ObjectPath {
+        self.prefix.child(format!("{}.md", slug))
+    }
+}
+
+#[async_trait]
+impl DataStore for ObjectDataStore {
+    /// Create
+    async fn create_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+        let markdown = serialize_post(postdata);
+        self.store
+            .put(&self.slug_to_key(&postdata.slug), markdown.into_bytes().into())
+            .await?;
+        log::trace!("{:?}", postdata);
+        Ok(postdata.clone())
+    }
+    /// Read
+    async fn read_post(
+        &self,
+        slug: &str,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+        let result = self.store.get(&self.slug_to_key(slug)).await?;
+        let bytes = result.bytes().await?;
+        let cont = String::from_utf8(bytes.to_vec())?;
+        let slug = slug.to_string();
+        let postdata = tokio::task::spawn_blocking(move || parse_post(slug, &cont)).await??;
+        log::trace!("{:?}", postdata);
+        Ok(postdata)
+    }
+    /// List
+    async fn list_posts(&self) -> Result<Vec<Slug>, Box<dyn std::error::Error + Send + Sync>> {
+        let metas = self
+            .store
+            .list(Some(&self.prefix))
+            .try_collect::<Vec<_>>()
+            .await?;
+        let slug_vec = metas
+            .into_iter()
+            .filter_map(|meta| {
+                meta.location
+                    .filename()
+                    .and_then(|name| name.strip_suffix(".md"))
+                    .map(|slug| slug.to_string())
+            })
+            .collect();
+        Ok(slug_vec)
+    }
+    /// Update
+    async fn update_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+        let markdown = serialize_post(postdata);
+        self.store
+            .put(&self.slug_to_key(&postdata.slug), markdown.into_bytes().into())
+            .await?;
+        log::trace!("{:?}", postdata);
+        Ok(postdata.clone())
+    }
+    /// Delete
+    async fn delete_post(
+        &self,
+        slug: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.store.delete(&self.slug_to_key(slug)).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+
+/// slug の型
+pub type Slug = String;
+
+/// Post のデータ
+#[derive(Clone, PartialEq, Debug)]
+pub struct PostData {
+    pub title: String,
+    pub slug: String,
+    pub content: String,
+    /// 公開日時。front matter に記載があれば設定される。
+    pub date: Option<chrono::DateTime<chrono::Utc>>,
+    /// タグ。front matter の `tags` に対応する。
+    pub tags: Vec<String>,
+}
+
+/// datastore の trait
+///
+/// axum / async-graphql のハンドラから呼ばれるため、各メソッドは future を返す。
+#[async_trait]
+pub trait DataStore: Send + Sync {
+    /// Create
+    async fn create_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>>;
+    /// Read
+    async fn read_post(
+        &self,
+        slug: &str,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>>;
+    /// List
+    async fn list_posts(&self) -> Result<Vec<Slug>, Box<dyn std::error::Error + Send + Sync>>;
+    /// Update
+    async fn update_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>>;
+    /// Delete
+    async fn delete_post(&self, slug: &str)
+        -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+use crate::datastore::post::{DataStore, PostData, Slug};
+
+/// datastore on PostgreSQL
+///
+/// `posts` テーブル (`slug` PK, `title`, `content`, `published_at`, `tags`,
+/// `created_at`, `updated_at`) に CRUD をマッピングする。
+pub struct PostgresDataStore {
+    pool: PgPool,
+}
+
+impl PostgresDataStore {
+    /// 接続文字列から datastore を構築する。
+    pub async fn new(url: &str) -> Result<PostgresDataStore, Box<dyn std::error::Error + Send + Sync>> {
+        let pool = PgPoolOptions::new().connect(url).await?;
+        Ok(PostgresDataStore { pool })
+    }
+}
+
+#[async_trait]
+impl DataStore for PostgresDataStore {
+    /// Create
+    async fn create_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            "INSERT INTO posts (slug, title, content, published_at, tags, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, now(), now())",
+        )
+        .bind(&postdata.slug)
+        .bind(&postdata.title)
+        .bind(&postdata.content)
+        .bind(postdata.date)
+        .bind(&postdata.tags)
+        .execute(&self.pool)
+        .await?;
+        log::trace!("{:?}", postdata);
+        Ok(postdata.clone())
+    }
+    /// Read
+    async fn read_post(
+        &self,
+        slug: &str,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+        let row: (
+            String,
+            String,
+            String,
+            Option<chrono::DateTime<chrono::Utc>>,
+            Vec<String>,
+        ) = sqlx::query_as(
+            "SELECT slug, title, content, published_at, tags FROM posts WHERE slug = $1",
+        )
+        .bind(slug)
+        .fetch_one(&self.pool)
+        .await?;
+        let (slug, title, content, date, tags) = row;
+        Ok(PostData {
+            title,
+            slug,
+            content,
+            date,
+            tags,
+        })
+    }
+    /// List
+    async fn list_posts(&self) -> Result<Vec<Slug>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT slug FROM posts")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|(slug,)| slug).collect())
+    }
+    /// Update
+    async fn update_post(
+        &self,
+        postdata: &PostData,
+    ) -> Result<PostData, Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            "UPDATE posts SET title = $2, content = $3, published_at = $4, tags = $5, \
+             updated_at = now() WHERE slug = $1",
+        )
+        .bind(&postdata.slug)
+        .bind(&postdata.title)
+        .bind(&postdata.content)
+        .bind(postdata.date)
+        .bind(&postdata.tags)
+        .execute(&self.pool)
+        .await?;
+        log::trace!("{:?}", postdata);
+        Ok(postdata.clone())
+    }
+    /// Delete
+    async fn delete_post(
+        &self,
+        slug: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("DELETE FROM posts WHERE slug = $1")
+            .bind(slug)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use rss::{ChannelBuilder, GuidBuilder, ItemBuilder};
+
+use crate::graphql::model::Storage;
+
+/// 保存されている Post から RSS 2.0 feed を生成する。
+///
+/// `date` を持つ Post を新しい順に並べ、`<item>` を組み立てる。
+pub async fn build_feed(
+    storage: &Storage,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let mut posts = storage.all_posts().await?;
+    // 新しい順。date 未設定の Post は末尾に回す。
+    posts.sort_by(|a, b| b.date.cmp(&a.date));
+
+    let items = posts
+        .into_iter()
+        .map(|post| {
+            let link = format!("/posts/{}", post.slug);
+            ItemBuilder::default()
+                .title(Some(post.title))
+                .link(Some(link.clone()))
+                .guid(Some(GuidBuilder::default().value(link).permalink(false).build()))
+                .description(Some(post.content))
+                .pub_date(post.date.map(|d| d.to_rfc2822()))
+                .build()
+        })
+        .collect::<Vec<_>>();
+
+    let channel = ChannelBuilder::default()
+        .title("rust-axum-async-graphql-blog")
+        .link("/")
+        .description("Posts feed")
+        .items(items)
+        .build();
+
+    Ok(channel.to_string())
+}
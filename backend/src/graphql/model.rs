@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use async_graphql::{
+    ComplexObject, Context, EmptySubscription, Object, Result, Schema, SimpleObject, Upload,
+};
+
+use crate::datastore::file::FileDataStore;
+use crate::datastore::memory::MemoryDataStore;
+use crate::datastore::object_store::ObjectDataStore;
+use crate::datastore::post::{DataStore, PostData};
+use crate::datastore::postgres::PostgresDataStore;
+use crate::mediastore::file::FileMediaStore;
+use crate::mediastore::media::MediaStore;
+use crate::render::{self, RenderStats};
+
+/// このサーバの GraphQL スキーマ
+pub type GraphQLSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// 起動時に選択する datastore の構成
+pub enum StorageConfig {
+    /// ファイルシステム上の posts ディレクトリ
+    File(String),
+    /// in-memory
+    Memory,
+    /// PostgreSQL 接続文字列
+    Postgres(String),
+    /// object storage の接続 URL (例: `s3://bucket/posts`)
+    ObjectStore(String),
+}
+
+/// resolver から参照する datastore のハンドル
+#[derive(Clone)]
+pub struct Storage(Arc<dyn DataStore>);
+
+impl Storage {
+    /// ファイルシステム上の datastore で Storage を構築する。
+    pub fn new(posts_dir: &str) -> Storage {
+        Storage(Arc::new(FileDataStore::new(posts_dir)))
+    }
+    /// 構成に従って backend を選択し Storage を構築する。
+    pub async fn from_config(
+        config: StorageConfig,
+    ) -> Result<Storage, Box<dyn std::error::Error + Send + Sync>> {
+        let store: Arc<dyn DataStore> = match config {
+            StorageConfig::File(dir) => Arc::new(FileDataStore::new(&dir)),
+            StorageConfig::Memory => Arc::new(MemoryDataStore::new()),
+            StorageConfig::Postgres(url) => Arc::new(PostgresDataStore::new(&url).await?),
+            StorageConfig::ObjectStore(url) => Arc::new(ObjectDataStore::new(&url)?),
+        };
+        Ok(Storage(store))
+    }
+    /// 全 Post を読み出す。feed 生成など resolver 外からも使う。
+    pub async fn all_posts(
+        &self,
+    ) -> Result<Vec<PostData>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut posts = Vec::new();
+        for slug in self.0.list_posts().await? {
+            posts.push(self.0.read_post(&slug).await?);
+        }
+        Ok(posts)
+    }
+}
+
+/// resolver / media ルートから参照する mediastore のハンドル
+#[derive(Clone)]
+pub struct MediaStorage(Arc<dyn MediaStore>);
+
+impl MediaStorage {
+    /// ファイルシステム上の mediastore で MediaStorage を構築する。
+    pub fn new(media_dir: &str) -> MediaStorage {
+        MediaStorage(Arc::new(FileMediaStore::new(media_dir)))
+    }
+    /// キーを指定してメディアのバイト列を読み出す。
+    pub async fn get_media(
+        &self,
+        key: &str,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        self.0.get_media(key).await
+    }
+}
+
+/// GraphQL に公開する Post
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Post {
+    pub title: String,
+    pub slug: String,
+    pub content: String,
+    pub date: Option<chrono::DateTime<chrono::Utc>>,
+    pub tags: Vec<String>,
+    /// content のレンダリング結果。1 クエリ内で高々 1 度だけ計算する。
+    #[graphql(skip)]
+    rendered: tokio::sync::OnceCell<(String, RenderStats)>,
+}
+
+#[ComplexObject]
+impl Post {
+    /// content を無害化済み HTML にレンダリングして返す。
+    async fn rendered_content(&self) -> Result<String> {
+        Ok(self.render().await?.0.clone())
+    }
+    /// renderedContent を生成したレンダリングの統計を返す。
+    async fn render_stats(&self) -> Result<RenderStats> {
+        Ok(self.render().await?.1.clone())
+    }
+}
+
+impl Post {
+    /// content を一度だけ comrak でレンダリングし、結果を使い回す。
+    ///
+    /// comrak は CPU バウンドなので `spawn_blocking` で実行する。
+    async fn render(&self) -> Result<&(String, RenderStats)> {
+        self.rendered
+            .get_or_try_init(|| async {
+                let content = self.content.clone();
+                Ok(tokio::task::spawn_blocking(move || render::render_markdown(&content)).await?)
+            })
+            .await
+    }
+}
+
+impl From<PostData> for Post {
+    fn from(postdata: PostData) -> Self {
+        let PostData {
+            title,
+            slug,
+            content,
+            date,
+            tags,
+        } = postdata;
+        Post {
+            title,
+            slug,
+            content,
+            date,
+            tags,
+            rendered: tokio::sync::OnceCell::new(),
+        }
+    }
+}
+
+/// Input for create / update
+#[derive(async_graphql::InputObject)]
+pub struct PostInput {
+    pub title: String,
+    pub slug: String,
+    pub content: String,
+    pub date: Option<chrono::DateTime<chrono::Utc>>,
+    #[graphql(default)]
+    pub tags: Vec<String>,
+}
+
+impl From<PostInput> for PostData {
+    fn from(input: PostInput) -> Self {
+        let PostInput {
+            title,
+            slug,
+            content,
+            date,
+            tags,
+        } = input;
+        PostData {
+            title,
+            slug,
+            content,
+            date,
+            tags,
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Post を一覧する。`tag` を指定するとそのタグを含む Post のみを返す。
+    async fn posts(&self, ctx: &Context<'_>, tag: Option<String>) -> Result<Vec<Post>> {
+        let storage = ctx.data_unchecked::<Storage>();
+        let posts = storage.all_posts().await?.into_iter();
+        let posts: Vec<Post> = match tag {
+            Some(tag) => posts
+                .filter(|p| p.tags.contains(&tag))
+                .map(Post::from)
+                .collect(),
+            None => posts.map(Post::from).collect(),
+        };
+        Ok(posts)
+    }
+    /// slug を指定して Post を読み出す。
+    async fn post(&self, ctx: &Context<'_>, slug: String) -> Result<Post> {
+        let storage = ctx.data_unchecked::<Storage>();
+        Ok(storage.0.read_post(&slug).await?.into())
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Create
+    async fn create_post(&self, ctx: &Context<'_>, input: PostInput) -> Result<Post> {
+        let storage = ctx.data_unchecked::<Storage>();
+        Ok(storage.0.create_post(&input.into()).await?.into())
+    }
+    /// Update
+    async fn update_post(&self, ctx: &Context<'_>, input: PostInput) -> Result<Post> {
+        let storage = ctx.data_unchecked::<Storage>();
+        Ok(storage.0.update_post(&input.into()).await?.into())
+    }
+    /// Delete
+    async fn delete_post(&self, ctx: &Context<'_>, slug: String) -> Result<bool> {
+        let storage = ctx.data_unchecked::<Storage>();
+        storage.0.delete_post(&slug).await?;
+        Ok(true)
+    }
+    /// 添付メディアをアップロードし、参照用の URL を返す。
+    async fn upload_media(&self, ctx: &Context<'_>, file: Upload) -> Result<String> {
+        let media = ctx.data_unchecked::<MediaStorage>();
+        let upload = file.value(ctx)?;
+        // Upload の中身は同期 File なので spawn_blocking で読み出す。
+        let bytes = tokio::task::spawn_blocking(move || {
+            use std::io::Read;
+            let mut content = upload.content;
+            let mut bytes = Vec::new();
+            content.read_to_end(&mut bytes).map(|_| bytes)
+        })
+        .await??;
+        let key = media.0.put_media(bytes).await?;
+        Ok(format!("/media/{}", key))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod datastore;
+pub mod feed;
+pub mod graphql;
+pub mod mediastore;
+pub mod render;
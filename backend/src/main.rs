@@ -8,8 +8,10 @@ use axum::Router;
 use hyper::Server;
 use tower_http::cors::CorsLayer;
 
+use axum::extract::Path;
+use rust_axum_async_graphql_blog::feed;
 use rust_axum_async_graphql_blog::graphql::model::{
-    GraphQLSchema, MutationRoot, QueryRoot, Storage,
+    GraphQLSchema, MediaStorage, MutationRoot, QueryRoot, Storage, StorageConfig,
 };
 
 async fn graphql_handler(schema: Extension<GraphQLSchema>, req: GraphQLRequest) -> GraphQLResponse {
@@ -20,19 +22,74 @@ async fn graphql_playground() -> impl IntoResponse {
     Html(playground_source(GraphQLPlaygroundConfig::new("/")))
 }
 
+async fn feed_handler(storage: Extension<Storage>) -> impl IntoResponse {
+    match feed::build_feed(&storage).await {
+        Ok(xml) => (
+            [(axum::http::header::CONTENT_TYPE, "application/rss+xml")],
+            xml,
+        )
+            .into_response(),
+        Err(e) => {
+            log::error!("failed to build feed: {}", e);
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+async fn media_handler(media: Extension<MediaStorage>, Path(key): Path<String>) -> impl IntoResponse {
+    match media.get_media(&key).await {
+        Ok(bytes) => {
+            // 保存されたバイト列から mime を推定し、画像などがインラインで表示されるようにする。
+            let mime = infer::get(&bytes)
+                .map(|t| t.mime_type())
+                .unwrap_or("application/octet-stream");
+            ([(axum::http::header::CONTENT_TYPE, mime)], bytes).into_response()
+        }
+        Err(e) => {
+            log::debug!("media not found {}: {}", key, e);
+            axum::http::StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let _ = pretty_env_logger::try_init();
 
+    // backend は環境変数 STORAGE_BACKEND で選択する (file / memory / postgres / object_store)。
+    let config = match std::env::var("STORAGE_BACKEND").as_deref() {
+        Ok("memory") => StorageConfig::Memory,
+        Ok("postgres") => StorageConfig::Postgres(
+            std::env::var("DATABASE_URL").expect("DATABASE_URL is required for the postgres backend"),
+        ),
+        Ok("object_store") => StorageConfig::ObjectStore(
+            std::env::var("OBJECT_STORE_URL")
+                .expect("OBJECT_STORE_URL is required for the object_store backend"),
+        ),
+        _ => StorageConfig::File(
+            std::env::var("POSTS_DIR").unwrap_or_else(|_| "./posts".to_string()),
+        ),
+    };
+    let storage = Storage::from_config(config)
+        .await
+        .expect("failed to initialize storage backend");
+
+    let media = MediaStorage::new(&std::env::var("MEDIA_DIR").unwrap_or_else(|_| "./media".to_string()));
+
     let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
-        .data(Storage::new("./posts"))
+        .data(storage.clone())
+        .data(media.clone())
         .finish();
 
     println!("Playground: http://localhost:8000");
 
     let app = Router::new()
         .route("/", get(graphql_playground).post(graphql_handler))
+        .route("/feed.xml", get(feed_handler))
+        .route("/media/:key", get(media_handler))
         .layer(Extension(schema))
+        .layer(Extension(storage))
+        .layer(Extension(media))
         .layer(CorsLayer::permissive());
 
     Server::bind(&"0.0.0.0:8000".parse().unwrap())
@@ -0,0 +1,70 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::mediastore::media::MediaStore;
+
+/// mediastore on file system
+///
+/// アップロードされたバイト列を `media/` ディレクトリ以下に content hash を
+/// ファイル名として保存する。同一内容は同じキーに収束する。
+pub struct FileMediaStore {
+    pub media_dir_path: PathBuf,
+}
+
+#[derive(PartialEq, Debug, thiserror::Error)]
+enum FileMediaStoreErrors {
+    #[error("InvalidKey")]
+    InvalidKey,
+}
+
+/// 呼び出し元由来のキーが空でなく、パス区切りや `..` を含まないことを確認する。
+///
+/// `..` やパス区切りを含むキーは media ディレクトリ外を指しうるため拒否する。
+fn validate_key(key: &str) -> Result<(), FileMediaStoreErrors> {
+    if key.is_empty() || key.contains('/') || key.contains('\\') || key.contains("..") {
+        return Err(FileMediaStoreErrors::InvalidKey);
+    }
+    Ok(())
+}
+
+impl FileMediaStore {
+    /// Constructor
+    pub fn new(media_dir: &str) -> FileMediaStore {
+        let media_dir_path = Path::new(media_dir).to_path_buf();
+        FileMediaStore { media_dir_path }
+    }
+    /// key から path を作成する。
+    fn key_to_path(&self, key: &str) -> PathBuf {
+        let path = self.media_dir_path.join(key);
+        log::trace!("{:?}", path);
+        path
+    }
+}
+
+#[async_trait]
+impl MediaStore for FileMediaStore {
+    async fn put_media(
+        &self,
+        bytes: Vec<u8>,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let key = format!("{:x}", Sha256::digest(&bytes));
+        tokio::fs::create_dir_all(&self.media_dir_path).await?;
+        let mut file = tokio::fs::File::create(self.key_to_path(&key)).await?;
+        file.write_all(&bytes).await?;
+        log::trace!("put media: {}", key);
+        Ok(key)
+    }
+    async fn get_media(
+        &self,
+        key: &str,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        validate_key(key)?;
+        let mut file = tokio::fs::File::open(self.key_to_path(key)).await?;
+        let mut bytes = Vec::new();
+        let _n = file.read_to_end(&mut bytes).await?;
+        Ok(bytes)
+    }
+}
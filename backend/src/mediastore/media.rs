@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+
+/// メディア (添付ファイル) を保存する trait
+///
+/// [`DataStore`](crate::datastore::post::DataStore) と対になる抽象で、
+/// アップロードされたバイト列を content hash をキーに保存し、参照するための slug を返す。
+#[async_trait]
+pub trait MediaStore: Send + Sync {
+    /// バイト列を保存し、参照用のキー (content hash) を返す。
+    async fn put_media(
+        &self,
+        bytes: Vec<u8>,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
+    /// キーを指定して保存済みのバイト列を読み出す。
+    async fn get_media(
+        &self,
+        key: &str,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>;
+}
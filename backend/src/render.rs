@@ -0,0 +1,58 @@
+use std::time::Instant;
+
+use async_graphql::SimpleObject;
+use comrak::plugins::syntect::SyntectAdapter;
+use comrak::{ComrakOptions, ComrakPlugins};
+
+/// Markdown のレンダリング統計
+#[derive(SimpleObject, Clone, Debug)]
+pub struct RenderStats {
+    /// パースにかかった時間 (マイクロ秒)
+    pub parse_time_us: u64,
+    /// レンダリングにかかった時間 (マイクロ秒)
+    pub render_time_us: u64,
+    /// 生成された HTML のバイト数
+    pub byte_size: u64,
+}
+
+/// GFM 拡張 (tables, strikethrough, autolinks, footnotes) を有効にした
+/// comrak のオプションを構築する。生の HTML は出力せずに省いて無害化する。
+fn comrak_options() -> ComrakOptions {
+    let mut options = ComrakOptions::default();
+    options.extension.table = true;
+    options.extension.strikethrough = true;
+    options.extension.autolink = true;
+    options.extension.footnotes = true;
+    // 生 HTML は通さない (サニタイズ)。
+    options.render.unsafe_ = false;
+    options
+}
+
+/// Markdown を無害化済み HTML に変換し、統計を返す。
+///
+/// comrak は CPU バウンドなので呼び出し側は `spawn_blocking` で実行すること。
+pub fn render_markdown(markdown: &str) -> (String, RenderStats) {
+    let options = comrak_options();
+
+    let parse_start = Instant::now();
+    let arena = comrak::Arena::new();
+    let root = comrak::parse_document(&arena, markdown, &options);
+    let parse_time_us = parse_start.elapsed().as_micros() as u64;
+
+    let adapter = SyntectAdapter::new("InspiredGitHub");
+    let mut plugins = ComrakPlugins::default();
+    plugins.render.codefence_syntax_highlighter = Some(&adapter);
+
+    let render_start = Instant::now();
+    let mut html = Vec::new();
+    comrak::format_html_with_plugins(root, &options, &mut html, &plugins).unwrap();
+    let render_time_us = render_start.elapsed().as_micros() as u64;
+
+    let html = String::from_utf8(html).unwrap();
+    let stats = RenderStats {
+        parse_time_us,
+        render_time_us,
+        byte_size: html.len() as u64,
+    };
+    (html, stats)
+}